@@ -10,14 +10,58 @@ use ink_env::Environment;
 /// in https://github.com/paritytech/substrate/blob/v3.0.0/frame/randomness-collective-flip/src/lib.rs
 pub const RF_DELAY: u32 = 81;
 
-/// Function to provide randomness to Candle Auction.  
+/// Source of randomness for Candle Auction.  
 /// Can be, for instance:
-///   1. `ink_env::random()` (implemented variant)
+///   1. `ink_env::random()` (implemented variant, see `CollectiveFlip`)
 ///   2. `rand_extension` (see Ink! contract examples)
 ///   3. whatever else you'd like to use
-pub fn random<T>(seed: &[u8]) -> (T::Hash, T::BlockNumber)
-where
-    T: Environment,
-{
-    ink_env::random::<T>(seed).expect("cannot get randomness!")
+pub trait RandomnessSource {
+    /// Code of the randomness source,
+    /// for verifiers to fetch the matching public randomness:  
+    ///   0 = randomness-collective-flip  
+    ///   1..255 = reserved for further randomness sources
+    const CODE: u8;
+
+    /// Get a random hash along with the block number since which it is known.
+    fn random<T>(seed: &[u8]) -> (T::Hash, T::BlockNumber)
+    where
+        T: Environment;
+}
+
+/// `ink_env::random()`, which in `substrate-contracts-node`
+/// is implemented with `pallet_collective_flip`.
+pub struct CollectiveFlip;
+
+impl RandomnessSource for CollectiveFlip {
+    const CODE: u8 = 0;
+
+    fn random<T>(seed: &[u8]) -> (T::Hash, T::BlockNumber)
+    where
+        T: Environment,
+    {
+        ink_env::random::<T>(seed).expect("cannot get randomness!")
+    }
+}
+
+/// Randomness source the candle is blown with.  
+/// Swap it for your own `RandomnessSource` implementation, e.g. a chain-extension VRF.
+pub type DefaultRandomness = CollectiveFlip;
+
+/// Code of the randomness source used (see `RandomnessSource::CODE`)
+pub const RANDOMNESS_SOURCE: u8 = <DefaultRandomness as RandomnessSource>::CODE;
+
+/// Map the random output to the `0..n` range with no modulo bias.  
+/// The output is read as a sequence of little-endian `u32` words,
+/// and the first one below the greatest multiple of `n` is reduced (rejection sampling).  
+/// Should all the words be rejected (which is next to impossible), the last one is reduced anyway.
+pub fn uniform_below(random: &[u8], n: u32) -> u32 {
+    let limit = (1u64 << 32) / u64::from(n) * u64::from(n);
+    let mut word = 0;
+    for w in random.chunks_exact(4) {
+        word = u32::from_le_bytes([w[0], w[1], w[2], w[3]]);
+        if u64::from(word) < limit {
+            break;
+        }
+    }
+    word % n
 }